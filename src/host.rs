@@ -0,0 +1,275 @@
+//! The host side of the plugin <-> host relationship: the `clap_host` vtable presented to
+//! plugins, and the per-instance bookkeeping needed to answer the plugin's queries honestly
+//! instead of just trusting whatever it claims.
+
+use clap_sys::ext::posix_fd_support::{clap_posix_fd_flags, CLAP_EXT_POSIX_FD_SUPPORT};
+use clap_sys::ext::thread_check::CLAP_EXT_THREAD_CHECK;
+use clap_sys::ext::timer_support::CLAP_EXT_TIMER_SUPPORT;
+use clap_sys::host::clap_host;
+use clap_sys::id::clap_id;
+use clap_sys::version::CLAP_VERSION;
+use crossbeam_utils::atomic::AtomicCell;
+use std::ffi::{c_char, c_void, CStr, CString};
+use std::os::unix::io::RawFd;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::ThreadId;
+use std::time::{Duration, Instant};
+
+mod event_loop;
+mod posix_fd_support;
+mod thread_check;
+mod timer_support;
+
+use event_loop::{FdRegistration, TimerRegistration};
+use posix_fd_support::CLAP_HOST_POSIX_FD_SUPPORT_VTABLE;
+use thread_check::CLAP_HOST_THREAD_CHECK_VTABLE;
+use timer_support::CLAP_HOST_TIMER_SUPPORT_VTABLE;
+
+use crate::plugin::instance::{PluginHandle, PluginStatus};
+
+/// A task a `clap_host` callback (e.g. `request_callback()`) can ask the main thread to handle.
+/// Nothing in this crate queues or handles these tasks: `request_callback()` does nothing, and
+/// [`InstanceState::run_event_loop_iteration()`] only dispatches timers and file descriptors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CallbackTask {
+    /// The plugin called `clap_host::request_callback()` and wants `clap_plugin::on_main_thread()`
+    /// to be called back on the main thread.
+    RequestCallback,
+}
+
+/// The host implementation this crate presents to CLAP plugins. Shared between every plugin
+/// instance created from it.
+#[derive(Debug)]
+pub struct Host {
+    name: CString,
+    vendor: CString,
+    url: CString,
+    version: CString,
+
+    /// The instances created from this host, used to e.g. broadcast host callbacks. Instances
+    /// register themselves here when they're created and unregister themselves when dropped.
+    instances: Mutex<Vec<Pin<Arc<InstanceState>>>>,
+}
+
+impl Host {
+    /// Create a new host. `name`/`vendor`/`url`/`version` are reported to plugins through the
+    /// `clap_host` struct.
+    pub fn new(name: &str, vendor: &str, url: &str, version: &str) -> Arc<Host> {
+        Arc::new(Host {
+            name: CString::new(name).expect("host name contained a null byte"),
+            vendor: CString::new(vendor).expect("host vendor contained a null byte"),
+            url: CString::new(url).expect("host url contained a null byte"),
+            version: CString::new(version).expect("host version contained a null byte"),
+
+            instances: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Register a newly created plugin instance with this host.
+    pub fn register_instance(&self, state: Pin<Arc<InstanceState>>) {
+        self.instances.lock().unwrap().push(state);
+    }
+
+    /// Unregister a plugin instance that is about to be destroyed.
+    pub fn unregister_instance(&self, state: Pin<Arc<InstanceState>>) {
+        self.instances
+            .lock()
+            .unwrap()
+            .retain(|other| !Arc::ptr_eq(other, &state));
+    }
+}
+
+/// Information about a single plugin instance stored on the host. This is what `clap_host`'s
+/// `host_data` points to, and it keeps track of things like the instance's lifecycle state, its
+/// registered timers and file descriptors, and the thread identities needed to answer
+/// `clap_host_thread_check` queries.
+#[derive(Debug)]
+pub struct InstanceState {
+    /// The `clap_host` vtable handed to the plugin on creation. `host_data` points back to this
+    /// `InstanceState`, which is why this object must stay pinned for its entire lifetime.
+    clap_host: clap_host,
+    host: Arc<Host>,
+
+    pub plugin: AtomicCell<Option<PluginHandle>>,
+    pub status: AtomicCell<PluginStatus>,
+
+    /// The thread this instance was created from. CLAP designates this the plugin's main thread.
+    main_thread_id: ThreadId,
+    /// The thread currently processing audio for this instance, if any. Populated for the
+    /// duration of each `ProcessingPlugin::process()` call, and cleared once it returns.
+    audio_thread_id: AtomicCell<Option<ThreadId>>,
+
+    /// The timers this instance has registered through `clap_host_timer_support`, driven by
+    /// [`run_event_loop_iteration()`][Self::run_event_loop_iteration()].
+    timers: Mutex<Vec<TimerRegistration>>,
+    /// The id that will be handed out to the next registered timer.
+    next_timer_id: AtomicU32,
+    /// The file descriptors this instance has registered through `clap_host_posix_fd_support`,
+    /// driven by [`run_event_loop_iteration()`][Self::run_event_loop_iteration()].
+    fds: Mutex<Vec<FdRegistration>>,
+}
+
+impl InstanceState {
+    /// Create a new, pinned `InstanceState` for an instance that's about to be created from
+    /// `host`. The calling thread becomes this instance's main thread.
+    pub fn new(host: Arc<Host>) -> Pin<Arc<Self>> {
+        let state = Arc::pin(InstanceState {
+            clap_host: clap_host {
+                clap_version: CLAP_VERSION,
+                host_data: std::ptr::null_mut(),
+                name: host.name.as_ptr(),
+                vendor: host.vendor.as_ptr(),
+                url: host.url.as_ptr(),
+                version: host.version.as_ptr(),
+                get_extension: Some(get_extension),
+                request_restart: Some(request_restart),
+                request_process: Some(request_process),
+                request_callback: Some(request_callback),
+            },
+            host,
+
+            plugin: AtomicCell::new(None),
+            status: AtomicCell::new(PluginStatus::Uninitialized),
+
+            main_thread_id: std::thread::current().id(),
+            audio_thread_id: AtomicCell::new(None),
+
+            timers: Mutex::new(Vec::new()),
+            next_timer_id: AtomicU32::new(0),
+            fds: Mutex::new(Vec::new()),
+        });
+
+        // SAFETY: Nothing else can have observed `clap_host.host_data` yet since `state` was just
+        //         created, and patching this field doesn't move the `InstanceState` itself, so
+        //         the `Pin` guarantee still holds
+        unsafe {
+            let arc = Pin::into_inner_unchecked(state);
+            let ptr = Arc::as_ptr(&arc) as *mut Self;
+            (*ptr).clap_host.host_data = ptr as *mut c_void;
+
+            Pin::new_unchecked(arc)
+        }
+    }
+
+    /// Get the raw pointer to the `clap_host` struct that was passed to the plugin on creation.
+    pub fn clap_host_ptr(&self) -> *const clap_host {
+        &self.clap_host
+    }
+
+    /// Get the `Host` this instance was created from, if the calling thread is this instance's
+    /// main thread. Returns `None` otherwise, since `Host` is not meant to be touched from other
+    /// threads.
+    pub fn host(&self) -> Option<&Host> {
+        self.is_main_thread().then_some(&self.host)
+    }
+
+    /// Whether the calling thread is this instance's designated main thread, i.e. the thread it
+    /// was created from.
+    pub fn is_main_thread(&self) -> bool {
+        std::thread::current().id() == self.main_thread_id
+    }
+
+    /// Whether the calling thread is this instance's current audio thread, i.e. the thread that
+    /// last called [`set_audio_thread()`][Self::set_audio_thread()] with `Some(..)` and hasn't
+    /// cleared it again yet.
+    pub fn is_audio_thread(&self) -> bool {
+        self.audio_thread_id.load() == Some(std::thread::current().id())
+    }
+
+    /// Record (or clear) which thread is currently allowed to call this instance's audio thread
+    /// functions. Called by `ProcessingPlugin::process()` around the actual `process()` call.
+    pub fn set_audio_thread(&self, thread_id: Option<ThreadId>) {
+        self.audio_thread_id.store(thread_id);
+    }
+
+    /// Register a new periodic timer with period `period_ms`, for `clap_host_timer_support`.
+    /// Returns the id assigned to the timer, which fires for the first time after `period_ms` and
+    /// is then rescheduled indefinitely until unregistered.
+    fn register_timer(&self, period_ms: u32) -> Option<clap_id> {
+        let id = self.next_timer_id.fetch_add(1, Ordering::Relaxed);
+        let period = Duration::from_millis(period_ms as u64);
+
+        self.timers.lock().unwrap().push(TimerRegistration {
+            id,
+            period,
+            next_deadline: Instant::now() + period,
+        });
+
+        Some(id)
+    }
+
+    /// Unregister a previously registered timer. Returns whether a timer with that id existed.
+    fn unregister_timer(&self, timer_id: clap_id) -> bool {
+        let mut timers = self.timers.lock().unwrap();
+        let len_before = timers.len();
+        timers.retain(|timer| timer.id != timer_id);
+
+        timers.len() != len_before
+    }
+
+    /// Register a file descriptor to be polled with `flags`, for `clap_host_posix_fd_support`.
+    /// Returns `false` if `fd` is already registered.
+    fn register_fd(&self, fd: RawFd, flags: clap_posix_fd_flags) -> bool {
+        let mut fds = self.fds.lock().unwrap();
+        if fds.iter().any(|registration| registration.fd == fd) {
+            return false;
+        }
+
+        fds.push(FdRegistration { fd, flags });
+
+        true
+    }
+
+    /// Change the flags a previously registered file descriptor is polled with. Returns `false`
+    /// if `fd` was not registered.
+    fn modify_fd(&self, fd: RawFd, flags: clap_posix_fd_flags) -> bool {
+        let mut fds = self.fds.lock().unwrap();
+        match fds.iter_mut().find(|registration| registration.fd == fd) {
+            Some(registration) => {
+                registration.flags = flags;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Unregister a previously registered file descriptor. Returns whether `fd` was registered.
+    fn unregister_fd(&self, fd: RawFd) -> bool {
+        let mut fds = self.fds.lock().unwrap();
+        let len_before = fds.len();
+        fds.retain(|registration| registration.fd != fd);
+
+        fds.len() != len_before
+    }
+}
+
+unsafe extern "C" fn get_extension(
+    host: *const clap_host,
+    extension_id: *const c_char,
+) -> *const c_void {
+    let extension_id = CStr::from_ptr(extension_id);
+
+    if extension_id == CLAP_EXT_THREAD_CHECK {
+        &CLAP_HOST_THREAD_CHECK_VTABLE as *const _ as *const c_void
+    } else if extension_id == CLAP_EXT_TIMER_SUPPORT {
+        &CLAP_HOST_TIMER_SUPPORT_VTABLE as *const _ as *const c_void
+    } else if extension_id == CLAP_EXT_POSIX_FD_SUPPORT {
+        &CLAP_HOST_POSIX_FD_SUPPORT_VTABLE as *const _ as *const c_void
+    } else {
+        std::ptr::null()
+    }
+}
+
+unsafe extern "C" fn request_restart(_host: *const clap_host) {
+    // Restarting the plugin is not currently supported
+}
+
+unsafe extern "C" fn request_process(_host: *const clap_host) {
+    // Processing is driven entirely by the caller, there is no background audio thread to wake up
+}
+
+unsafe extern "C" fn request_callback(_host: *const clap_host) {
+    // TODO: Queue a `CallbackTask::RequestCallback` for the main thread event loop to pick up
+}
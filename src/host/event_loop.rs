@@ -0,0 +1,164 @@
+//! A minimal main-thread event loop that drives the timers and posix-fds plugins register through
+//! [`clap_host_timer_support`][super::timer_support] and
+//! [`clap_host_posix_fd_support`][super::posix_fd_support], so GUI-less plugins (and GUI plugins
+//! between paint events) still get ticked.
+
+use anyhow::Result;
+use clap_sys::ext::posix_fd_support::{
+    clap_posix_fd_flags, CLAP_POSIX_FD_ERROR, CLAP_POSIX_FD_READ, CLAP_POSIX_FD_WRITE,
+};
+use clap_sys::id::clap_id;
+use std::os::unix::io::RawFd;
+use std::time::{Duration, Instant};
+
+use super::InstanceState;
+use crate::plugin::ext::posix_fd_support::PosixFDSupport;
+use crate::plugin::ext::timer_support::TimerSupport;
+use crate::plugin::instance::Plugin;
+
+/// A timer a plugin registered through `clap_host_timer_support::register_timer()`.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct TimerRegistration {
+    pub id: clap_id,
+    pub period: Duration,
+    pub next_deadline: Instant,
+}
+
+/// A file descriptor a plugin registered through `clap_host_posix_fd_support::register_fd()`.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct FdRegistration {
+    pub fd: RawFd,
+    pub flags: clap_posix_fd_flags,
+}
+
+impl InstanceState {
+    /// Block until the nearest registered timer is due or one of the registered fds becomes
+    /// ready, then dispatch the corresponding `on_timer()`/`on_fd()` callbacks on `plugin`. If
+    /// neither any timers nor any fds are registered, this returns immediately without blocking.
+    ///
+    /// This must be called repeatedly from the main thread for as long as the plugin should keep
+    /// ticking, e.g. in a loop around whatever else the main thread is doing.
+    pub fn run_event_loop_iteration(&self, plugin: &Plugin) -> Result<()> {
+        let timeout = {
+            let timers = self.timers.lock().unwrap();
+            timers
+                .iter()
+                .map(|timer| timer.next_deadline.saturating_duration_since(Instant::now()))
+                .min()
+        };
+
+        let mut poll_fds: Vec<libc::pollfd> = {
+            let fds = self.fds.lock().unwrap();
+            fds.iter()
+                .map(|fd| libc::pollfd {
+                    fd: fd.fd,
+                    events: clap_flags_to_poll_events(fd.flags),
+                    revents: 0,
+                })
+                .collect()
+        };
+
+        if !poll_fds.is_empty() || timeout.is_some() {
+            // Round up so `poll(2)` never wakes up before the nearest deadline
+            let timeout_ms = timeout.map_or(-1, |timeout| {
+                ((timeout.as_nanos() + 999_999) / 1_000_000).min(i32::MAX as u128) as i32
+            });
+            let result = unsafe {
+                libc::poll(
+                    poll_fds.as_mut_ptr(),
+                    poll_fds.len() as libc::nfds_t,
+                    timeout_ms,
+                )
+            };
+            if result < 0 {
+                let error = std::io::Error::last_os_error();
+                anyhow::bail!("'poll(2)' failed: {error}");
+            }
+        }
+
+        self.dispatch_expired_timers(plugin)?;
+        self.dispatch_ready_fds(plugin, &poll_fds)?;
+
+        Ok(())
+    }
+
+    fn dispatch_expired_timers(&self, plugin: &Plugin) -> Result<()> {
+        let now = Instant::now();
+        let expired: Vec<clap_id> = {
+            let mut timers = self.timers.lock().unwrap();
+            let mut expired = Vec::new();
+            for timer in timers.iter_mut() {
+                if timer.next_deadline <= now {
+                    expired.push(timer.id);
+                    timer.next_deadline = now + timer.period;
+                }
+            }
+
+            expired
+        };
+
+        if expired.is_empty() {
+            return Ok(());
+        }
+
+        let timer_support: TimerSupport = plugin
+            .get_extension()
+            .ok_or_else(|| anyhow::anyhow!("The plugin no longer supports 'timer-support'"))?;
+        for timer_id in expired {
+            timer_support.on_timer(plugin, timer_id);
+        }
+
+        Ok(())
+    }
+
+    fn dispatch_ready_fds(&self, plugin: &Plugin, poll_fds: &[libc::pollfd]) -> Result<()> {
+        let ready: Vec<(RawFd, clap_posix_fd_flags)> = poll_fds
+            .iter()
+            .filter(|poll_fd| poll_fd.revents != 0)
+            .map(|poll_fd| (poll_fd.fd, poll_events_to_clap_flags(poll_fd.revents)))
+            .collect();
+
+        if ready.is_empty() {
+            return Ok(());
+        }
+
+        let posix_fd_support: PosixFDSupport = plugin
+            .get_extension()
+            .ok_or_else(|| anyhow::anyhow!("The plugin no longer supports 'posix-fd-support'"))?;
+        for (fd, flags) in ready {
+            posix_fd_support.on_fd(plugin, fd, flags);
+        }
+
+        Ok(())
+    }
+}
+
+fn clap_flags_to_poll_events(flags: clap_posix_fd_flags) -> libc::c_short {
+    let mut events = 0;
+    if flags & CLAP_POSIX_FD_READ != 0 {
+        events |= libc::POLLIN;
+    }
+    if flags & CLAP_POSIX_FD_WRITE != 0 {
+        events |= libc::POLLOUT;
+    }
+    if flags & CLAP_POSIX_FD_ERROR != 0 {
+        events |= libc::POLLERR;
+    }
+
+    events as libc::c_short
+}
+
+fn poll_events_to_clap_flags(revents: libc::c_short) -> clap_posix_fd_flags {
+    let mut flags = 0;
+    if revents & libc::POLLIN != 0 {
+        flags |= CLAP_POSIX_FD_READ;
+    }
+    if revents & libc::POLLOUT != 0 {
+        flags |= CLAP_POSIX_FD_WRITE;
+    }
+    if revents & (libc::POLLERR | libc::POLLHUP | libc::POLLNVAL) != 0 {
+        flags |= CLAP_POSIX_FD_ERROR;
+    }
+
+    flags
+}
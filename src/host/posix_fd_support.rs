@@ -0,0 +1,42 @@
+//! The host side of the `clap_host_posix_fd_support` extension: lets a plugin register file
+//! descriptors it wants polled, which the [event loop][super::event_loop] then dispatches to the
+//! plugin's
+//! [`PosixFDSupport::on_fd()`][crate::plugin::ext::posix_fd_support::PosixFDSupport::on_fd()].
+
+use clap_sys::ext::posix_fd_support::{clap_host_posix_fd_support, clap_posix_fd_flags};
+use clap_sys::host::clap_host;
+use std::os::unix::io::RawFd;
+
+use super::InstanceState;
+
+/// The vtable returned from `clap_host::get_extension()` when a plugin asks for
+/// `CLAP_EXT_POSIX_FD_SUPPORT`.
+pub(super) static CLAP_HOST_POSIX_FD_SUPPORT_VTABLE: clap_host_posix_fd_support =
+    clap_host_posix_fd_support {
+        register_fd: Some(register_fd),
+        modify_fd: Some(modify_fd),
+        unregister_fd: Some(unregister_fd),
+    };
+
+unsafe extern "C" fn register_fd(
+    host: *const clap_host,
+    fd: RawFd,
+    flags: clap_posix_fd_flags,
+) -> bool {
+    let state = &*((*host).host_data as *const InstanceState);
+    state.register_fd(fd, flags)
+}
+
+unsafe extern "C" fn modify_fd(
+    host: *const clap_host,
+    fd: RawFd,
+    flags: clap_posix_fd_flags,
+) -> bool {
+    let state = &*((*host).host_data as *const InstanceState);
+    state.modify_fd(fd, flags)
+}
+
+unsafe extern "C" fn unregister_fd(host: *const clap_host, fd: RawFd) -> bool {
+    let state = &*((*host).host_data as *const InstanceState);
+    state.unregister_fd(fd)
+}
@@ -0,0 +1,25 @@
+//! The host side of the `clap_host_thread_check` extension: lets a plugin ask whether it is
+//! currently running on its main thread or its audio thread, so it can validate its own thread
+//! usage instead of taking the caller's word for it.
+
+use clap_sys::ext::thread_check::clap_host_thread_check;
+use clap_sys::host::clap_host;
+
+use super::InstanceState;
+
+/// The vtable returned from `clap_host::get_extension()` when a plugin asks for
+/// `CLAP_EXT_THREAD_CHECK`.
+pub(super) static CLAP_HOST_THREAD_CHECK_VTABLE: clap_host_thread_check = clap_host_thread_check {
+    is_main_thread: Some(is_main_thread),
+    is_audio_thread: Some(is_audio_thread),
+};
+
+unsafe extern "C" fn is_main_thread(host: *const clap_host) -> bool {
+    let state = &*((*host).host_data as *const InstanceState);
+    state.is_main_thread()
+}
+
+unsafe extern "C" fn is_audio_thread(host: *const clap_host) -> bool {
+    let state = &*((*host).host_data as *const InstanceState);
+    state.is_audio_thread()
+}
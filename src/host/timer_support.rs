@@ -0,0 +1,38 @@
+//! The host side of the `clap_host_timer_support` extension: lets a plugin register/unregister
+//! periodic timers, which the [event loop][super::event_loop] then dispatches to the plugin's
+//! [`TimerSupport::on_timer()`][crate::plugin::ext::timer_support::TimerSupport::on_timer()].
+
+use clap_sys::ext::timer_support::clap_host_timer_support;
+use clap_sys::host::clap_host;
+use clap_sys::id::clap_id;
+
+use super::InstanceState;
+
+/// The vtable returned from `clap_host::get_extension()` when a plugin asks for
+/// `CLAP_EXT_TIMER_SUPPORT`.
+pub(super) static CLAP_HOST_TIMER_SUPPORT_VTABLE: clap_host_timer_support =
+    clap_host_timer_support {
+        register_timer: Some(register_timer),
+        unregister_timer: Some(unregister_timer),
+    };
+
+unsafe extern "C" fn register_timer(
+    host: *const clap_host,
+    period_ms: u32,
+    timer_id: *mut clap_id,
+) -> bool {
+    let state = &*((*host).host_data as *const InstanceState);
+
+    match state.register_timer(period_ms) {
+        Some(id) => {
+            *timer_id = id;
+            true
+        }
+        None => false,
+    }
+}
+
+unsafe extern "C" fn unregister_timer(host: *const clap_host, timer_id: clap_id) -> bool {
+    let state = &*((*host).host_data as *const InstanceState);
+    state.unregister_timer(timer_id)
+}
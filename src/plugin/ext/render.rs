@@ -0,0 +1,71 @@
+//! Abstractions for interacting with the `render` extension.
+
+use clap_sys::ext::render::{
+    clap_plugin_render, CLAP_EXT_RENDER, CLAP_RENDER_OFFLINE, CLAP_RENDER_REALTIME,
+};
+use std::ffi::CStr;
+use std::ptr::NonNull;
+
+use super::Extension;
+use crate::plugin::assert_plugin_state_initialized;
+use crate::plugin::instance::Plugin;
+use crate::util::unsafe_clap_call;
+
+/// Abstraction for the `render` extension covering the main thread functionality.
+#[derive(Debug)]
+pub struct Render {
+    render: NonNull<clap_plugin_render>,
+}
+
+impl Extension<&Plugin> for Render {
+    const EXTENSION_ID: &'static CStr = CLAP_EXT_RENDER;
+
+    type Struct = clap_plugin_render;
+
+    fn new(extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            render: extension_struct,
+        }
+    }
+}
+
+/// The rendering mode a plugin can be switched into through [`Render::set()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RenderMode {
+    /// The plugin should behave as if it was running in a live, realtime context, e.g. during
+    /// normal playback.
+    Realtime,
+    /// The plugin is free to use slower, higher quality algorithms (e.g. for time-stretching or
+    /// dithering) since it's not bound by a hard realtime deadline, e.g. during a bounce or
+    /// export pass.
+    Offline,
+}
+
+impl From<RenderMode> for clap_sys::ext::render::clap_plugin_render_mode {
+    fn from(mode: RenderMode) -> Self {
+        match mode {
+            RenderMode::Realtime => CLAP_RENDER_REALTIME,
+            RenderMode::Offline => CLAP_RENDER_OFFLINE,
+        }
+    }
+}
+
+impl Render {
+    /// Query whether the plugin has a hard realtime requirement and can thus not be used for
+    /// offline rendering. This is a main thread function, and it is only valid to call once the
+    /// plugin has been initialized.
+    pub fn has_hard_realtime_requirement(&self, plugin: &Plugin) -> bool {
+        assert_plugin_state_initialized!(plugin);
+
+        unsafe_clap_call! { self.render.as_ptr()=>has_hard_realtime_requirement(plugin.as_ptr()) }
+    }
+
+    /// Ask the plugin to switch to `mode`. Returns whether the plugin accepted the new mode. This
+    /// is a main thread function, and it is only valid to call once the plugin has been
+    /// initialized.
+    pub fn set(&self, plugin: &Plugin, mode: RenderMode) -> bool {
+        assert_plugin_state_initialized!(plugin);
+
+        unsafe_clap_call! { self.render.as_ptr()=>set(plugin.as_ptr(), mode.into()) }
+    }
+}
@@ -0,0 +1,109 @@
+//! Abstractions for interacting with the `state` extension.
+
+use anyhow::Result;
+use clap_sys::ext::state::{clap_plugin_state, CLAP_EXT_STATE};
+use clap_sys::stream::{clap_istream, clap_ostream};
+use std::ffi::{c_void, CStr};
+use std::io::{Read, Write};
+use std::ptr::NonNull;
+
+use super::Extension;
+use crate::plugin::assert_plugin_state_initialized;
+use crate::plugin::instance::Plugin;
+use crate::util::unsafe_clap_call;
+
+/// Abstraction for the `state` extension covering the main thread functionality.
+#[derive(Debug)]
+pub struct State {
+    state: NonNull<clap_plugin_state>,
+}
+
+impl Extension<&Plugin> for State {
+    const EXTENSION_ID: &'static CStr = CLAP_EXT_STATE;
+
+    type Struct = clap_plugin_state;
+
+    fn new(extension_struct: NonNull<Self::Struct>) -> Self {
+        Self {
+            state: extension_struct,
+        }
+    }
+}
+
+impl State {
+    /// Ask the plugin to save its state to `writer`. This is a main thread function, and it is
+    /// only valid to call once the plugin has been initialized.
+    pub fn save(&self, plugin: &Plugin, writer: impl Write) -> Result<()> {
+        assert_plugin_state_initialized!(plugin);
+
+        let mut writer = writer;
+        let writer: Box<&mut dyn Write> = Box::new(&mut writer);
+        let ctx = Box::into_raw(writer);
+        let ostream = clap_ostream {
+            ctx: ctx as *mut c_void,
+            write: Some(ostream_write),
+        };
+
+        let result = unsafe_clap_call! { self.state.as_ptr()=>save(plugin.as_ptr(), &ostream) };
+
+        // SAFETY: `ctx` was created by `Box::into_raw()` above and hasn't been freed yet.
+        drop(unsafe { Box::from_raw(ctx) });
+
+        if result {
+            Ok(())
+        } else {
+            anyhow::bail!("'clap_plugin_state::save()' returned false")
+        }
+    }
+
+    /// Ask the plugin to restore a previously saved state from `reader`. This is a main thread
+    /// function, and it is only valid to call once the plugin has been initialized.
+    pub fn load(&self, plugin: &Plugin, reader: impl Read) -> Result<()> {
+        assert_plugin_state_initialized!(plugin);
+
+        let mut reader = reader;
+        let reader: Box<&mut dyn Read> = Box::new(&mut reader);
+        let ctx = Box::into_raw(reader);
+        let istream = clap_istream {
+            ctx: ctx as *mut c_void,
+            read: Some(istream_read),
+        };
+
+        let result = unsafe_clap_call! { self.state.as_ptr()=>load(plugin.as_ptr(), &istream) };
+
+        // SAFETY: `ctx` was created by `Box::into_raw()` above and hasn't been freed yet.
+        drop(unsafe { Box::from_raw(ctx) });
+
+        if result {
+            Ok(())
+        } else {
+            anyhow::bail!("'clap_plugin_state::load()' returned false")
+        }
+    }
+}
+
+/// The `write` callback for the `clap_ostream` passed to [`State::save()`]. `ctx` points to the
+/// boxed `&mut dyn Write` the caller's writer was boxed into. Copies `size` bytes from `buffer`
+/// into the writer, returning the number of bytes written or `-1` on error.
+unsafe extern "C" fn ostream_write(stream: *const clap_ostream, buffer: *const c_void, size: u64) -> i64 {
+    let writer = &mut *((*stream).ctx as *mut &mut dyn Write);
+    let buffer = std::slice::from_raw_parts(buffer as *const u8, size as usize);
+
+    match writer.write(buffer) {
+        Ok(written) => written as i64,
+        Err(_) => -1,
+    }
+}
+
+/// The `read` callback for the `clap_istream` passed to [`State::load()`]. `ctx` points to the
+/// boxed `&mut dyn Read` the caller's reader was boxed into. Fills `buffer` from the reader,
+/// returning the number of bytes read, `0` at EOF, or `-1` on error.
+unsafe extern "C" fn istream_read(stream: *const clap_istream, buffer: *mut c_void, size: u64) -> i64 {
+    let reader = &mut *((*stream).ctx as *mut &mut dyn Read);
+    let buffer = std::slice::from_raw_parts_mut(buffer as *mut u8, size as usize);
+
+    match reader.read(buffer) {
+        Ok(read) => read as i64,
+        Err(_) => -1,
+    }
+}
@@ -1,10 +1,12 @@
 //! Abstractions for single CLAP plugin instances for main thread interactions.
 
-use anyhow::Result;
 use clap_sys::factory::plugin_factory::clap_plugin_factory;
 use clap_sys::plugin::clap_plugin;
+use clap_sys::process::clap_process;
 use std::ffi::CStr;
+use std::fmt;
 use std::marker::PhantomData;
+use std::mem::ManuallyDrop;
 use std::ops::Deref;
 use std::pin::Pin;
 use std::ptr::NonNull;
@@ -12,12 +14,85 @@ use std::sync::Arc;
 
 use super::ext::Extension;
 use super::library::PluginLibrary;
-use super::{assert_plugin_state_eq, assert_plugin_state_initialized};
+use super::assert_plugin_state_initialized;
 use crate::host::{CallbackTask, Host, InstanceState};
 use crate::util::unsafe_clap_call;
 
 pub mod process;
 
+/// The error type returned by [`Plugin`]'s lifecycle functions (and by
+/// [`ActivatedPlugin::start_processing()`], [`ProcessingPlugin::process()`], and the lifecycle
+/// functions on [`CheckedPlugin`]). Unlike the `anyhow::Result`
+/// used elsewhere in this crate, this is a dedicated enum so a host can programmatically
+/// distinguish the different ways a lifecycle operation can fail, e.g. a plugin that refused
+/// activation at a given sample rate from one that failed to instantiate in the first place, and
+/// react accordingly (say, retry `activate()` with a different buffer size) instead of just
+/// logging a string.
+#[derive(Debug)]
+pub enum PluginInstanceError {
+    /// `clap_plugin_factory::create_plugin()` returned a null pointer.
+    CreationFailed,
+    /// `clap_plugin::init()` returned `false`.
+    InitFailed,
+    /// `clap_plugin::activate()` returned `false`.
+    ActivationFailed,
+    /// `clap_plugin::start_processing()` returned `false`.
+    StartProcessingFailed,
+    /// `clap_plugin::process()` returned `CLAP_PROCESS_ERROR`.
+    ProcessingFailed,
+    /// The operation requires an activated plugin, but the plugin has not been activated (or has
+    /// since been deactivated again).
+    DeactivatedPlugin,
+    /// The operation requires a deactivated plugin, but the plugin has already been activated.
+    AlreadyActivatedPlugin,
+    /// The plugin was not in the lifecycle state the operation required it to be in.
+    InvalidState {
+        expected: PluginStatus,
+        actual: PluginStatus,
+    },
+}
+
+impl fmt::Display for PluginInstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PluginInstanceError::CreationFailed => write!(
+                f,
+                "'clap_plugin_factory::create_plugin()' returned a null pointer"
+            ),
+            PluginInstanceError::InitFailed => {
+                write!(f, "'clap_plugin::init()' returned false")
+            }
+            PluginInstanceError::ActivationFailed => {
+                write!(f, "'clap_plugin::activate()' returned false")
+            }
+            PluginInstanceError::StartProcessingFailed => {
+                write!(f, "'clap_plugin::start_processing()' returned false")
+            }
+            PluginInstanceError::ProcessingFailed => {
+                write!(f, "'clap_plugin::process()' returned 'CLAP_PROCESS_ERROR'")
+            }
+            PluginInstanceError::DeactivatedPlugin => write!(
+                f,
+                "this operation requires an activated plugin, but the plugin is deactivated"
+            ),
+            PluginInstanceError::AlreadyActivatedPlugin => write!(
+                f,
+                "this operation requires a deactivated plugin, but the plugin is already activated"
+            ),
+            PluginInstanceError::InvalidState { expected, actual } => write!(
+                f,
+                "expected the plugin to be in the '{expected:?}' state, but it was in the \
+                 '{actual:?}' state"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for PluginInstanceError {}
+
+/// A convenience alias for `Result`s returned from [`Plugin`]'s lifecycle functions.
+pub type Result<T> = std::result::Result<T, PluginInstanceError>;
+
 /// A `Send+Sync` wrapper around `*const clap_plugin`.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(transparent)]
@@ -26,12 +101,21 @@ pub struct PluginHandle(pub NonNull<clap_plugin>);
 unsafe impl Send for PluginHandle {}
 unsafe impl Sync for PluginHandle {}
 
-/// A CLAP plugin instance. The plugin will be deinitialized when this object is dropped. All
-/// functions here are callable only from the main thread. Use the
-/// [`on_audio_thread()`][Self::on_audio_thread()] method to spawn an audio thread.
+/// A CLAP plugin instance that has been created but may or may not have been initialized yet. The
+/// plugin will be deinitialized when this object is dropped. All functions here are callable only
+/// from the main thread.
 ///
-/// All functions on `Plugin` and the objects created from it will panic if the plugin is not in the
-/// correct state.
+/// Activation and processing are modeled as a type-state chain instead of runtime checks:
+/// [`activate()`][Self::activate()] consumes this object and returns an [`ActivatedPlugin`], which
+/// in turn can be turned into a [`ProcessingPlugin`] with
+/// [`start_processing()`][ActivatedPlugin::start_processing()]. This makes illegal transitions
+/// (like deactivating a plugin that was never activated) a compile error instead of a panic. For
+/// callers that need to carry a plugin's lifecycle state dynamically, e.g. in a homogeneous
+/// collection, [`CheckedPlugin`] mirrors the same operations with runtime assertions instead.
+///
+/// The lifecycle functions on `Plugin` and [`CheckedPlugin`] return a [`PluginInstanceError`] if
+/// the plugin is not in the correct state. Other functions, like
+/// [`get_extension()`][Self::get_extension()], panic instead.
 #[derive(Debug)]
 pub struct Plugin {
     handle: PluginHandle,
@@ -46,10 +130,10 @@ pub struct Plugin {
     _send_sync_marker: PhantomData<*const ()>,
 }
 
-/// The plugin's current lifecycle state. This is checked extensively to ensure that the plugin is
-/// in the correct state, and things like double activations can't happen. `Plugin` and
-/// `PluginAudioThread` will drop down to the previous state automatically when the object is
-/// dropped and the stop processing or deactivate functions have not yet been calle.d
+/// The plugin's current lifecycle state. The type-state API ([`Plugin`], [`ActivatedPlugin`], and
+/// [`ProcessingPlugin`]) uses this only for informational purposes (e.g. so the host extensions
+/// can answer questions about an instance's state); [`CheckedPlugin`] additionally uses it to
+/// check transitions at runtime the way this crate used to for every plugin.
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum PluginStatus {
     #[default]
@@ -75,10 +159,19 @@ impl Deref for PluginSendWrapper {
 
 impl Drop for Plugin {
     fn drop(&mut self) {
-        // Make sure the plugin is in the correct state before it gets destroyed
+        // By construction a live `Plugin` going through the type-state chain is never in the
+        // `Activated` or `Processing` state here: `activate()` consumes `self` into an
+        // `ActivatedPlugin`, which deactivates the plugin before this destructor ever gets to run.
+        // `CheckedPlugin` is the exception, since it flips this `Plugin`'s shared status directly
+        // instead of going through the chain: if it is dropped while `Processing`, its own `Drop`
+        // panics without deactivating first, and this destructor still runs during that unwind.
+        // Guard against destroying a plugin that was never deactivated.
         match self.status() {
             PluginStatus::Uninitialized | PluginStatus::Deactivated => (),
-            PluginStatus::Activated => self.deactivate(),
+            PluginStatus::Activated => {
+                unsafe_clap_call! { self.as_ptr()=>deactivate(self.as_ptr()) };
+                self.state.status.store(PluginStatus::Deactivated);
+            }
             status @ PluginStatus::Processing => panic!(
                 "The plugin was in an invalid state '{status:?}' when the instance got dropped, \
                  this is a clap-validator bug"
@@ -120,9 +213,7 @@ impl Plugin {
             factory=>create_plugin(factory, state.clap_host_ptr(), plugin_id.as_ptr())
         };
         if plugin.is_null() {
-            anyhow::bail!(
-                "'clap_plugin_factory::create_plugin({plugin_id:?})' returned a null pointer"
-            );
+            return Err(PluginInstanceError::CreationFailed);
         }
 
         // We can only register the plugin instance with the host now because we did not have a
@@ -157,11 +248,25 @@ impl Plugin {
         self.state.status.load()
     }
 
+    /// Panic if the calling thread is not the thread this instance was created from. This is a
+    /// cross-check against the `clap_host_thread_check` bookkeeping in [`InstanceState`], on top
+    /// of the [`PhantomData`] marker that merely prevents `Plugin` from being `Send`/`Sync`: a
+    /// plugin calling a main-thread-only function from some other thread is caught here instead
+    /// of silently proceeding.
+    fn assert_main_thread(&self) {
+        assert!(
+            self.state.is_main_thread(),
+            "A main-thread-only function was called from a thread other than the one this plugin \
+             instance was created from, this is a clap-validator bug or invalid plugin behavior"
+        );
+    }
+
     /// Get the _main thread_ extension abstraction for the extension `T`, if the plugin supports
     /// this extension. Returns `None` if it does not. The plugin needs to be initialized using
     /// [`init()`][Self::init()] before this may be called.
     pub fn get_extension<'a, T: Extension<&'a Self>>(&'a self) -> Option<T> {
         assert_plugin_state_initialized!(self);
+        self.assert_main_thread();
 
         let extension_ptr = unsafe_clap_call! {
             self.as_ptr()=>get_extension(self.as_ptr(), T::EXTENSION_ID.as_ptr())
@@ -178,26 +283,47 @@ impl Plugin {
 
     /// Initialize the plugin. This needs to be called before doing anything else.
     pub fn init(&self) -> Result<()> {
-        assert_plugin_state_eq!(self, PluginStatus::Uninitialized);
+        let actual = self.status();
+        if actual != PluginStatus::Uninitialized {
+            return Err(PluginInstanceError::InvalidState {
+                expected: PluginStatus::Uninitialized,
+                actual,
+            });
+        }
+        self.assert_main_thread();
 
         if unsafe_clap_call! { self.as_ptr()=>init(self.as_ptr()) } {
             self.state.status.store(PluginStatus::Deactivated);
             Ok(())
         } else {
-            anyhow::bail!("'clap_plugin::init()' returned false")
+            Err(PluginInstanceError::InitFailed)
         }
     }
 
-    /// Activate the plugin. Returns an error if the plugin returned `false`. See
+    /// Activate the plugin, consuming this object and returning an [`ActivatedPlugin`] that only
+    /// exposes the operations valid on an activated plugin. Returns an error (and drops the
+    /// plugin) if the plugin returned `false`. See
     /// [plugin.h](https://github.com/free-audio/clap/blob/main/include/clap/plugin.h) for the
     /// preconditions.
     pub fn activate(
-        &self,
+        self,
         sample_rate: f64,
         min_buffer_size: usize,
         max_buffer_size: usize,
-    ) -> Result<()> {
-        assert_plugin_state_eq!(self, PluginStatus::Deactivated);
+    ) -> Result<ActivatedPlugin> {
+        match self.status() {
+            PluginStatus::Deactivated => (),
+            PluginStatus::Activated | PluginStatus::Processing => {
+                return Err(PluginInstanceError::AlreadyActivatedPlugin)
+            }
+            actual => {
+                return Err(PluginInstanceError::InvalidState {
+                    expected: PluginStatus::Deactivated,
+                    actual,
+                })
+            }
+        }
+        self.assert_main_thread();
 
         // Apparently 0 is invalid here
         assert!(min_buffer_size >= 1);
@@ -211,20 +337,341 @@ impl Plugin {
             )
         } {
             self.state.status.store(PluginStatus::Activated);
-            Ok(())
+            Ok(ActivatedPlugin(ManuallyDrop::new(self)))
+        } else {
+            Err(PluginInstanceError::ActivationFailed)
+        }
+    }
+}
+
+/// A [`Plugin`] that has been activated with [`Plugin::activate()`]. This only exposes the
+/// operations that are valid on an activated plugin: starting audio processing with
+/// [`start_processing()`][Self::start_processing()], or deactivating it again with
+/// [`deactivate()`][Self::deactivate()]. Dropping this object without calling `deactivate()`
+/// deactivates the plugin automatically before the underlying [`Plugin`] is destroyed.
+#[derive(Debug)]
+pub struct ActivatedPlugin(ManuallyDrop<Plugin>);
+
+impl Deref for ActivatedPlugin {
+    type Target = Plugin;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for ActivatedPlugin {
+    fn drop(&mut self) {
+        unsafe_clap_call! { self.as_ptr()=>deactivate(self.as_ptr()) };
+        self.0.state.status.store(PluginStatus::Deactivated);
+
+        // SAFETY: `self.0` is never accessed again after this, this is the only place other than
+        //         `deactivate()` where the `ManuallyDrop` is allowed to run
+        unsafe { ManuallyDrop::drop(&mut self.0) };
+    }
+}
+
+impl ActivatedPlugin {
+    /// Start audio processing, consuming this object and returning a [`ProcessingPlugin`] that
+    /// exposes [`process()`][ProcessingPlugin::process()]. See
+    /// [plugin.h](https://github.com/free-audio/clap/blob/main/include/clap/plugin.h) for the
+    /// preconditions.
+    pub fn start_processing(mut self) -> Result<ProcessingPlugin> {
+        self.assert_main_thread();
+
+        if unsafe_clap_call! { self.as_ptr()=>start_processing(self.as_ptr()) } {
+            self.0.state.status.store(PluginStatus::Processing);
+
+            // SAFETY: `self` is forgotten right after this, so `self.0` is never dropped twice
+            let activated = unsafe { ManuallyDrop::take(&mut self.0) };
+            std::mem::forget(self);
+
+            Ok(ProcessingPlugin(ManuallyDrop::new(ActivatedPlugin(
+                ManuallyDrop::new(activated),
+            ))))
         } else {
-            anyhow::bail!("'clap_plugin::activate()' returned false")
+            Err(PluginInstanceError::StartProcessingFailed)
         }
     }
 
-    /// Deactivate the plugin. See
+    /// Deactivate the plugin, consuming this object and returning the underlying [`Plugin`] so it
+    /// can be activated again. See
     /// [plugin.h](https://github.com/free-audio/clap/blob/main/include/clap/plugin.h) for the
     /// preconditions.
-    pub fn deactivate(&self) {
-        assert_plugin_state_eq!(self, PluginStatus::Activated);
+    pub fn deactivate(mut self) -> Plugin {
+        self.assert_main_thread();
+
+        unsafe_clap_call! { self.as_ptr()=>deactivate(self.as_ptr()) };
+        self.0.state.status.store(PluginStatus::Deactivated);
+
+        // SAFETY: `self` is forgotten right after this, so `self.0` is never dropped twice
+        let plugin = unsafe { ManuallyDrop::take(&mut self.0) };
+        std::mem::forget(self);
+
+        plugin
+    }
+}
+
+/// A [`Plugin`] that has been activated and is currently processing audio, created with
+/// [`ActivatedPlugin::start_processing()`]. This only exposes
+/// [`process()`][Self::process] (implemented in the [`process`] module) and
+/// [`stop_processing()`][Self::stop_processing()]. Dropping this object without calling
+/// `stop_processing()` stops processing (and then deactivates) automatically.
+#[derive(Debug)]
+pub struct ProcessingPlugin(ManuallyDrop<ActivatedPlugin>);
+
+impl Deref for ProcessingPlugin {
+    type Target = ActivatedPlugin;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for ProcessingPlugin {
+    fn drop(&mut self) {
+        unsafe_clap_call! { self.as_ptr()=>stop_processing(self.as_ptr()) };
+        self.0.state.status.store(PluginStatus::Activated);
+
+        // SAFETY: `self.0` is never accessed again after this, this is the only place other than
+        //         `stop_processing()` where the `ManuallyDrop` is allowed to run
+        unsafe { ManuallyDrop::drop(&mut self.0) };
+    }
+}
+
+impl ProcessingPlugin {
+    /// Run a single processing block: the plugin reads `process.audio_inputs` and writes
+    /// `process.audio_outputs`. Returns the [`ProcessStatus`][process::ProcessStatus] the plugin
+    /// reported, along with the per-port
+    /// [`AudioPortProcessingInfo`][process::AudioPortProcessingInfo] for every input and output
+    /// port in `process`, so the caller can read back things like the constant mask and latency
+    /// the plugin reported for each port. CLAP designates this an audio thread function; the
+    /// calling thread is recorded as the instance's audio thread for the duration of the call. See
+    /// [plugin.h](https://github.com/free-audio/clap/blob/main/include/clap/plugin.h) for the
+    /// preconditions.
+    pub fn process<'a>(
+        &self,
+        process: &'a mut clap_process,
+    ) -> Result<process::ProcessOutcome<'a>> {
+        // `process()` is the one function in this type-state chain that CLAP designates an audio
+        // thread function rather than a main thread one: record the calling thread for the
+        // duration of the call so `clap_host_thread_check::is_audio_thread()` can answer honestly.
+        self.state
+            .set_audio_thread(Some(std::thread::current().id()));
+        let status_result =
+            unsafe_clap_call! { self.as_ptr()=>process(self.as_ptr(), process) }.try_into();
+        self.state.set_audio_thread(None);
+        let status = status_result?;
+
+        // SAFETY: `process`'s `audio_inputs`/`audio_outputs` point to `audio_inputs_count`/
+        //         `audio_outputs_count` valid buffers for at least `'a`, since `process` itself is
+        //         borrowed for `'a` and the returned `AudioPortProcessingInfo`s are tied to the same
+        //         lifetime, so they cannot outlive it
+        let input_ports =
+            unsafe { process::ports_from_raw(process.audio_inputs, process.audio_inputs_count) };
+        let output_ports = unsafe {
+            process::ports_from_raw(
+                process.audio_outputs as *const _,
+                process.audio_outputs_count,
+            )
+        };
+
+        Ok(process::ProcessOutcome {
+            status,
+            input_ports,
+            output_ports,
+        })
+    }
+
+    /// Stop audio processing, consuming this object and returning the underlying
+    /// [`ActivatedPlugin`] so it can be deactivated or started again.
+    pub fn stop_processing(mut self) -> ActivatedPlugin {
+        self.assert_main_thread();
+
+        unsafe_clap_call! { self.as_ptr()=>stop_processing(self.as_ptr()) };
+        self.0.state.status.store(PluginStatus::Activated);
+
+        // SAFETY: `self` is forgotten right after this, so `self.0` is never dropped twice
+        let activated = unsafe { ManuallyDrop::take(&mut self.0) };
+        std::mem::forget(self);
+
+        activated
+    }
+}
+
+/// A runtime-checked alternative to the [`Plugin`]/[`ActivatedPlugin`]/[`ProcessingPlugin`]
+/// type-state chain, for callers that need to carry a plugin's lifecycle state dynamically, e.g.
+/// in a homogeneous collection, instead of encoding it in the type. Every transition is checked at
+/// runtime and reports a [`PluginInstanceError`] on misuse instead of preventing it at compile
+/// time, exactly like this crate used to before the type-state API was introduced.
+#[derive(Debug)]
+pub struct CheckedPlugin(Plugin);
+
+impl Deref for CheckedPlugin {
+    type Target = Plugin;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl Drop for CheckedPlugin {
+    fn drop(&mut self) {
+        match self.0.status() {
+            PluginStatus::Uninitialized | PluginStatus::Deactivated => (),
+            PluginStatus::Activated => self
+                .deactivate()
+                .expect("deactivating an activated plugin cannot fail"),
+            status @ PluginStatus::Processing => panic!(
+                "The plugin was in an invalid state '{status:?}' when the instance got dropped, \
+                 this is a clap-validator bug"
+            ),
+        }
+    }
+}
+
+impl CheckedPlugin {
+    /// Wrap an uninitialized or deactivated [`Plugin`] so its lifecycle can be driven dynamically.
+    pub fn new(plugin: Plugin) -> Self {
+        Self(plugin)
+    }
+
+    /// See [`Plugin::activate()`].
+    pub fn activate(
+        &self,
+        sample_rate: f64,
+        min_buffer_size: usize,
+        max_buffer_size: usize,
+    ) -> Result<()> {
+        match self.0.status() {
+            PluginStatus::Deactivated => (),
+            PluginStatus::Activated | PluginStatus::Processing => {
+                return Err(PluginInstanceError::AlreadyActivatedPlugin)
+            }
+            actual => {
+                return Err(PluginInstanceError::InvalidState {
+                    expected: PluginStatus::Deactivated,
+                    actual,
+                })
+            }
+        }
+        self.assert_main_thread();
+
+        assert!(min_buffer_size >= 1);
+
+        if unsafe_clap_call! {
+            self.as_ptr()=>activate(
+                self.as_ptr(),
+                sample_rate,
+                min_buffer_size as u32,
+                max_buffer_size as u32,
+            )
+        } {
+            self.0.state.status.store(PluginStatus::Activated);
+            Ok(())
+        } else {
+            Err(PluginInstanceError::ActivationFailed)
+        }
+    }
+
+    /// See [`ActivatedPlugin::deactivate()`].
+    pub fn deactivate(&self) -> Result<()> {
+        match self.0.status() {
+            PluginStatus::Activated => (),
+            PluginStatus::Uninitialized | PluginStatus::Deactivated => {
+                return Err(PluginInstanceError::DeactivatedPlugin)
+            }
+            actual => {
+                return Err(PluginInstanceError::InvalidState {
+                    expected: PluginStatus::Activated,
+                    actual,
+                })
+            }
+        }
+        self.assert_main_thread();
 
         unsafe_clap_call! { self.as_ptr()=>deactivate(self.as_ptr()) };
 
-        self.state.status.store(PluginStatus::Deactivated);
+        self.0.state.status.store(PluginStatus::Deactivated);
+        Ok(())
+    }
+
+    /// See [`ActivatedPlugin::start_processing()`].
+    pub fn start_processing(&self) -> Result<()> {
+        match self.0.status() {
+            PluginStatus::Activated => (),
+            PluginStatus::Uninitialized | PluginStatus::Deactivated => {
+                return Err(PluginInstanceError::DeactivatedPlugin)
+            }
+            actual => {
+                return Err(PluginInstanceError::InvalidState {
+                    expected: PluginStatus::Activated,
+                    actual,
+                })
+            }
+        }
+        self.assert_main_thread();
+
+        if unsafe_clap_call! { self.as_ptr()=>start_processing(self.as_ptr()) } {
+            self.0.state.status.store(PluginStatus::Processing);
+            Ok(())
+        } else {
+            Err(PluginInstanceError::StartProcessingFailed)
+        }
+    }
+
+    /// See [`ProcessingPlugin::process()`].
+    pub fn process<'a>(
+        &self,
+        process: &'a mut clap_process,
+    ) -> Result<process::ProcessOutcome<'a>> {
+        let actual = self.0.status();
+        if actual != PluginStatus::Processing {
+            return Err(PluginInstanceError::InvalidState {
+                expected: PluginStatus::Processing,
+                actual,
+            });
+        }
+
+        // See `ProcessingPlugin::process()` for why the audio thread is recorded around the call
+        self.state
+            .set_audio_thread(Some(std::thread::current().id()));
+        let status_result =
+            unsafe_clap_call! { self.as_ptr()=>process(self.as_ptr(), process) }.try_into();
+        self.state.set_audio_thread(None);
+        let status = status_result?;
+
+        // SAFETY: see `ProcessingPlugin::process()`
+        let input_ports =
+            unsafe { process::ports_from_raw(process.audio_inputs, process.audio_inputs_count) };
+        let output_ports = unsafe {
+            process::ports_from_raw(
+                process.audio_outputs as *const _,
+                process.audio_outputs_count,
+            )
+        };
+
+        Ok(process::ProcessOutcome {
+            status,
+            input_ports,
+            output_ports,
+        })
+    }
+
+    /// See [`ProcessingPlugin::stop_processing()`].
+    pub fn stop_processing(&self) -> Result<()> {
+        let actual = self.0.status();
+        if actual != PluginStatus::Processing {
+            return Err(PluginInstanceError::InvalidState {
+                expected: PluginStatus::Processing,
+                actual,
+            });
+        }
+        self.assert_main_thread();
+
+        unsafe_clap_call! { self.as_ptr()=>stop_processing(self.as_ptr()) };
+
+        self.0.state.status.store(PluginStatus::Activated);
+        Ok(())
     }
 }
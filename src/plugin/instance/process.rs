@@ -0,0 +1,175 @@
+//! Abstractions for per-port audio processing information: reading back what a plugin reported
+//! about an output port after a `clap_plugin::process()` call, and telling a plugin about the
+//! state of an input port before one.
+
+use clap_sys::process::{
+    clap_audio_buffer, clap_process_status, CLAP_PROCESS_CONTINUE,
+    CLAP_PROCESS_CONTINUE_IF_NOT_QUIET, CLAP_PROCESS_SLEEP, CLAP_PROCESS_TAIL,
+};
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use super::PluginInstanceError;
+
+/// The number of channels a `clap_audio_buffer::constant_mask` bitfield can describe.
+const MAX_CHANNELS: u32 = 64;
+
+/// A bitmask over `clap_audio_buffer::constant_mask`, one bit per channel. A set bit means that
+/// channel holds a single constant value (DC, or silence) for the entire processing block.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ConstantMask(u64);
+
+impl ConstantMask {
+    /// Wrap a raw `constant_mask` value.
+    pub fn new(mask: u64) -> Self {
+        Self(mask)
+    }
+
+    /// The raw bitmask, one bit per channel, as stored in `clap_audio_buffer::constant_mask`.
+    pub fn to_bits(self) -> u64 {
+        self.0
+    }
+
+    /// Whether `channel` is constant for the duration of this block.
+    pub fn is_channel_constant(self, channel: u32) -> bool {
+        assert!(
+            channel < MAX_CHANNELS,
+            "The constant mask can only describe {MAX_CHANNELS} channels, got channel {channel}"
+        );
+
+        self.0 & (1 << channel) != 0
+    }
+
+    /// Mark `channel` as constant (or not) for the duration of this block.
+    pub fn set_channel_constant(&mut self, channel: u32, constant: bool) {
+        assert!(
+            channel < MAX_CHANNELS,
+            "The constant mask can only describe {MAX_CHANNELS} channels, got channel {channel}"
+        );
+
+        if constant {
+            self.0 |= 1 << channel;
+        } else {
+            self.0 &= !(1 << channel);
+        }
+    }
+}
+
+/// Per-port information read from, or written to, a single `clap_audio_buffer` passed as part of
+/// a `clap_process` struct. After a `process()` call the host can use this to read back an output
+/// port's [`constant_mask()`][Self::constant_mask()] and skip silent/constant channels in
+/// downstream mixing, and read the [`latency()`][Self::latency()] the plugin reports for that
+/// port. The same wrapper lets the host set the constant mask on an input port it knows to be
+/// DC/silent before calling `process()`, letting cooperating plugins skip work on those channels.
+///
+/// The `'a` lifetime ties this to the `clap_process` (and the buffer arrays it points to) that was
+/// passed to the `process()` call this was built from, so it cannot outlive the buffers it reads
+/// from and writes to.
+#[derive(Debug)]
+pub struct AudioPortProcessingInfo<'a> {
+    buffer: NonNull<clap_audio_buffer>,
+    _marker: PhantomData<&'a mut clap_audio_buffer>,
+}
+
+impl<'a> AudioPortProcessingInfo<'a> {
+    /// Wrap a raw `clap_audio_buffer` belonging to a `clap_process` struct passed to or received
+    /// from `clap_plugin::process()`.
+    ///
+    /// # Safety
+    ///
+    /// `buffer` must point to a valid, initialized `clap_audio_buffer` for the entire lifetime
+    /// `'a`.
+    pub unsafe fn new(buffer: NonNull<clap_audio_buffer>) -> Self {
+        Self {
+            buffer,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of channels in this port.
+    pub fn channel_count(&self) -> u32 {
+        unsafe { self.buffer.as_ref() }.channel_count
+    }
+
+    /// The latency, in samples, this port introduces, as reported by the plugin.
+    pub fn latency(&self) -> u32 {
+        unsafe { self.buffer.as_ref() }.latency
+    }
+
+    /// Which of this port's channels were constant (DC, or silent) for this block, as reported by
+    /// the plugin. Only meaningful for output ports after `process()` has returned.
+    pub fn constant_mask(&self) -> ConstantMask {
+        ConstantMask::new(unsafe { self.buffer.as_ref() }.constant_mask)
+    }
+
+    /// Tell the plugin which of this port's channels are constant (DC, or silent) for this block.
+    /// Only meaningful for input ports before `process()` is called; cooperating plugins can use
+    /// this to skip processing those channels.
+    pub fn set_constant_mask(&mut self, mask: ConstantMask) {
+        unsafe { self.buffer.as_mut() }.constant_mask = mask.to_bits();
+    }
+}
+
+/// Build the per-port [`AudioPortProcessingInfo`]s for every port in a `clap_process`'s
+/// `audio_inputs` or `audio_outputs` array.
+///
+/// # Safety
+///
+/// `buffers` must point to `count` valid, initialized `clap_audio_buffer`s for the entire
+/// lifetime `'a`.
+pub(crate) unsafe fn ports_from_raw<'a>(
+    buffers: *const clap_audio_buffer,
+    count: u32,
+) -> Vec<AudioPortProcessingInfo<'a>> {
+    (0..count)
+        .map(|i| {
+            AudioPortProcessingInfo::new(NonNull::new(buffers.add(i as usize) as *mut _).unwrap())
+        })
+        .collect()
+}
+
+/// What the plugin reported about the state of the audio stream after a `process()` call, as
+/// returned by `clap_plugin::process()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStatus {
+    /// The plugin wants to continue processing, and may produce non-silent output even if all
+    /// inputs are silent.
+    Continue,
+    /// The plugin wants to continue processing as long as its output remains non-silent; the host
+    /// may stop calling `process()` once it goes quiet.
+    ContinueIfNotQuiet,
+    /// The plugin has no more need for input, but still has a (known) tail of audio left to
+    /// output, e.g. a reverb or delay winding down.
+    Tail,
+    /// The plugin has no more audio to produce and may be put to sleep until the host has new
+    /// input for it.
+    Sleep,
+}
+
+impl TryFrom<clap_process_status> for ProcessStatus {
+    type Error = PluginInstanceError;
+
+    fn try_from(status: clap_process_status) -> Result<Self, Self::Error> {
+        match status {
+            CLAP_PROCESS_CONTINUE => Ok(ProcessStatus::Continue),
+            CLAP_PROCESS_CONTINUE_IF_NOT_QUIET => Ok(ProcessStatus::ContinueIfNotQuiet),
+            CLAP_PROCESS_TAIL => Ok(ProcessStatus::Tail),
+            CLAP_PROCESS_SLEEP => Ok(ProcessStatus::Sleep),
+            // Includes `CLAP_PROCESS_ERROR` as well as any value the plugin isn't supposed to
+            // return at all.
+            _ => Err(PluginInstanceError::ProcessingFailed),
+        }
+    }
+}
+
+/// The result of a single `clap_plugin::process()` call. Borrows from the `clap_process` that was
+/// passed to `process()`, so it cannot outlive the buffers it was built from.
+#[derive(Debug)]
+pub struct ProcessOutcome<'a> {
+    /// Whether the plugin wants to keep processing, and if so, under what conditions.
+    pub status: ProcessStatus,
+    /// Per-port info for the ports in `clap_process::audio_inputs`, in order.
+    pub input_ports: Vec<AudioPortProcessingInfo<'a>>,
+    /// Per-port info for the ports in `clap_process::audio_outputs`, in order.
+    pub output_ports: Vec<AudioPortProcessingInfo<'a>>,
+}